@@ -0,0 +1,134 @@
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+use sdl2::Sdl;
+use sdl2::EventPump;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::Color;
+use sdl2::render::WindowCanvas;
+
+use anyhow::Result;
+use anyhow::anyhow;
+
+/// Render framerate; a state's own simulation rate is independent and is its own concern
+pub const FPS: u64 = 144;
+const FRAME_DURATION: Duration = Duration::from_millis(1000 / FPS);
+
+/// Per-frame mouse state, since SDL only reports buttons/position when polled, not as events
+pub struct Input {
+    pub mouse_x: i32,
+    pub mouse_y: i32,
+    pub lmb_pressed: bool,
+    pub rmb_pressed: bool,
+}
+
+/// A swappable screen driving the main loop
+pub trait AppState {
+    fn handle_event(&mut self, event: &Event);
+    fn update(&mut self, dt: Duration, input: &Input);
+    fn render(&mut self, canvas: &mut WindowCanvas) -> Result<()>;
+}
+
+/// Configures an `App` before it opens a window
+pub struct AppBuilder {
+    title: String,
+    width: u32,
+    height: u32,
+    steps_per_second: u64,
+}
+
+impl AppBuilder {
+    pub fn new(title: &str) -> Self {
+        AppBuilder {
+            title: title.to_string(),
+            width: 800,
+            height: 600,
+            steps_per_second: 12,
+        }
+    }
+
+    pub fn resolution(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Sets the initial simulation rate a state is constructed with; see `App::steps_per_second`
+    pub fn steps_per_second(mut self, steps_per_second: u64) -> Self {
+        self.steps_per_second = steps_per_second;
+        self
+    }
+
+    pub fn build(self) -> Result<App> {
+        let sdl_context = sdl2::init().map_err(|e| anyhow!(e))?;
+        let video_subsystem = sdl_context.video().map_err(|e| anyhow!(e))?;
+
+        let window = video_subsystem.window(&self.title, self.width, self.height)
+            .position_centered()
+            .build()?;
+
+        let canvas = window.into_canvas().build()?;
+        let event_pump = sdl_context.event_pump().map_err(|e| anyhow!(e))?;
+
+        Ok(App {
+            _sdl_context: sdl_context,
+            canvas,
+            event_pump,
+            steps_per_second: self.steps_per_second,
+        })
+    }
+}
+
+/// Owns the window, canvas and event pump, and drives whichever `AppState` it's given
+pub struct App {
+    _sdl_context: Sdl,
+    canvas: WindowCanvas,
+    event_pump: EventPump,
+    steps_per_second: u64,
+}
+
+impl App {
+    /// The initial simulation rate configured on the `AppBuilder`, for states that need it
+    pub fn steps_per_second(&self) -> u64 {
+        self.steps_per_second
+    }
+
+    pub fn run(&mut self, state: &mut dyn AppState) {
+        'running: loop {
+            let frame_time = Instant::now();
+
+            for event in self.event_pump.poll_iter() {
+                match event {
+                    Event::Quit { .. } | Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
+                        break 'running;
+                    },
+                    _ => state.handle_event(&event),
+                }
+            }
+
+            let mouse_state = self.event_pump.mouse_state();
+            let input = Input {
+                mouse_x: mouse_state.x(),
+                mouse_y: mouse_state.y(),
+                lmb_pressed: mouse_state.left(),
+                rmb_pressed: mouse_state.right(),
+            };
+
+            state.update(FRAME_DURATION, &input);
+
+            self.canvas.set_draw_color(Color::RGB(0, 0, 0));
+            self.canvas.clear();
+
+            let _ = state.render(&mut self.canvas);
+
+            self.canvas.present();
+
+            // Ensure the game is running at the right framerate
+            if let Some(d) = FRAME_DURATION.checked_sub(frame_time.elapsed()) {
+                thread::sleep(d);
+            }
+        }
+    }
+}
@@ -0,0 +1,541 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+use std::fs;
+
+use sdl2::pixels::Color;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::rect::Rect;
+use sdl2::render::WindowCanvas;
+
+use anyhow::Result;
+use anyhow::bail;
+
+use crate::app::AppState;
+use crate::app::Input;
+use crate::app::FPS;
+
+const DIFFS: &[(i32, i32)] = &[
+    (-1, -1), (-1, 0), (-1, 1),
+    (0, -1),           (0, 1),
+    (1, -1),  (1, 0),  (1, 1)
+];
+
+const CELL_SIZE: i32 = 10;
+
+const PATTERN_FILE: &str = "pattern.rle";
+
+const MIN_STEPS_PER_SECOND: u64 = 1; // Keeps FPS / steps_per_second from ever dividing by zero
+const MAX_STEPS_PER_SECOND: u64 = FPS;
+
+/// A few built-in life-like rules to cycle through at runtime
+const RULESETS: &[&str] = &["B3/S23", "B36/S23", "B1357/S1357"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Coord {
+    x: i32,
+    y: i32,
+}
+
+trait Draw {
+    fn draw(&self, canvas: &mut WindowCanvas, x: i32, y: i32) -> Result<()>;
+}
+
+/// Fades a cell's color from white (newly born) toward blue-green as it survives longer
+fn age_to_color(age: u32) -> Color {
+    const MAX_AGE: f32 = 50.0;
+    let t = (age as f32).min(MAX_AGE) / MAX_AGE;
+
+    let r = 255.0 * (1.0 - t);
+    let g = 255.0 * (1.0 - t) + 160.0 * t;
+    let b = 255.0 * (1.0 - t) + 220.0 * t;
+
+    Color::RGB(r as u8, g as u8, b as u8)
+}
+
+impl Draw for HashMap<Coord, u32> {
+    fn draw(&self, canvas: &mut WindowCanvas, x: i32, y: i32) -> Result<()> {
+        for (c, age) in self.iter() {
+            canvas.set_draw_color(age_to_color(*age));
+            let draw_result = canvas.fill_rect(Rect::new(
+                x + c.x * CELL_SIZE,
+                y + c.y * CELL_SIZE,
+                CELL_SIZE as u32,
+                CELL_SIZE as u32
+            ));
+
+            if let Err(_) = draw_result {
+                bail!("Couldn't draw");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Assigns all the neighbour indices to the given reference
+fn neighbours(cell: Coord, neighbours: &mut [Coord; 8]) {
+    for (i, (dx, dy)) in DIFFS.iter().enumerate() {
+        neighbours[i] = Coord {
+            x: cell.x + dx,
+            y: cell.y + dy,
+        };
+    }
+}
+
+/// Returns the neighbour count for each position
+fn neighbour_counts(cells: &HashMap<Coord, u32>) -> HashMap<Coord, usize> {
+    let mut counts = HashMap::new();
+
+    // Loop for each alive cell
+    for c in cells.keys() {
+        let mut nei = [Coord { x: 0, y: 0 }; 8];
+        neighbours(*c, &mut nei);
+        for n in nei {
+            // Increment neighbour reference count
+            *counts.entry(n).or_insert(0) += 1
+        }
+    }
+
+    counts
+}
+
+/// Converts the mouse position to grid coordinates
+fn mouse_to_grid(x: i32, y: i32, cam_x: i32, cam_y: i32) -> Coord {
+    Coord {
+        x: (x + cam_x) / CELL_SIZE,
+        y: (y + cam_y) / CELL_SIZE,
+    }
+}
+
+/// Rasterizes every grid coordinate on the line between `from` and `to` (inclusive), using
+/// Bresenham's algorithm, so fast mouse drags don't leave gaps between sampled positions.
+fn line_between(from: Coord, to: Coord) -> Vec<Coord> {
+    let mut points = Vec::new();
+
+    let mut x0 = from.x;
+    let mut y0 = from.y;
+    let x1 = to.x;
+    let y1 = to.y;
+
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+
+    loop {
+        points.push(Coord { x: x0, y: y0 });
+
+        if x0 == x1 && y0 == y1 {
+            break;
+        }
+
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x0 += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y0 += sy;
+        }
+    }
+
+    points
+}
+
+/// A small xorshift64 PRNG, good enough for seeding random soups without pulling in a crate
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng { state: seed | 1 }
+    }
+
+    fn from_time() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x2545F4914F6CDD1D);
+
+        Rng::new(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+
+    /// Returns a value in `[0, 1)`
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+}
+
+/// Fills every cell in `[min_x, max_x] x [min_y, max_y]` with probability `density`
+fn seed_soup(cells: &mut HashMap<Coord, u32>, rng: &mut Rng, min_x: i32, min_y: i32, max_x: i32, max_y: i32, density: f64) {
+    for x in min_x..=max_x {
+        for y in min_y..=max_y {
+            if rng.next_f64() < density {
+                cells.insert(Coord { x, y }, 0);
+            }
+        }
+    }
+}
+
+/// A life-like automaton rule in B/S notation, e.g. `"B3/S23"` for Conway's Game of Life
+struct Rule {
+    birth: HashSet<usize>,
+    survive: HashSet<usize>,
+}
+
+impl Rule {
+    /// Parses a standard `"B.../S..."` rule string, e.g. `"B36/S23"` for HighLife
+    fn parse(notation: &str) -> Rule {
+        let mut parts = notation.split('/');
+        let b_part = parts.next().unwrap_or("");
+        let s_part = parts.next().unwrap_or("");
+
+        let digits = |part: &str, prefix: char| {
+            part.chars()
+                .filter(|c| *c != prefix)
+                .filter_map(|c| c.to_digit(10))
+                .map(|d| d as usize)
+                .collect::<HashSet<usize>>()
+        };
+
+        Rule {
+            birth: digits(b_part, 'B'),
+            survive: digits(s_part, 'S'),
+        }
+    }
+}
+
+/// Generates the new generation of cells according to `rule`, ageing survivors by one
+fn step(cells: &HashMap<Coord, u32>, rule: &Rule) -> HashMap<Coord, u32> {
+    let mut next_gen = HashMap::new();
+
+    for (cell, count) in neighbour_counts(cells) {
+        let alive = cells.get(&cell);
+        let survives = alive.is_some() && rule.survive.contains(&count);
+        let born = alive.is_none() && rule.birth.contains(&count);
+
+        if born {
+            next_gen.insert(cell, 0);
+        } else if survives {
+            next_gen.insert(cell, alive.unwrap() + 1);
+        }
+    }
+
+    next_gen
+}
+
+/// Appends a single RLE run (`<count><tag>`, omitting the count when it's 1) to `line`
+fn push_rle_run(line: &mut String, count: u32, tag: char) {
+    if count > 1 {
+        line.push_str(&count.to_string());
+    }
+    line.push(tag);
+}
+
+/// Encodes the live cells into the standard Run Length Encoded `.rle` pattern format,
+/// tagging the header with `rule`'s B/S notation
+fn to_rle(cells: &HashMap<Coord, u32>, rule: &str) -> String {
+    if cells.is_empty() {
+        return String::new();
+    }
+
+    let min_x = cells.keys().map(|c| c.x).min().unwrap();
+    let max_x = cells.keys().map(|c| c.x).max().unwrap();
+    let min_y = cells.keys().map(|c| c.y).min().unwrap();
+    let max_y = cells.keys().map(|c| c.y).max().unwrap();
+
+    let mut out = format!("x = {}, y = {}, rule = {}\n", max_x - min_x + 1, max_y - min_y + 1, rule);
+
+    let mut body = String::new();
+    for y in min_y..=max_y {
+        let mut run_tag = 'b';
+        let mut run_len = 0;
+
+        for x in min_x..=max_x {
+            let tag = if cells.contains_key(&Coord { x, y }) { 'o' } else { 'b' };
+
+            if run_len > 0 && tag != run_tag {
+                push_rle_run(&mut body, run_len, run_tag);
+                run_len = 0;
+            }
+
+            run_tag = tag;
+            run_len += 1;
+        }
+
+        // Trailing dead cells don't need to be encoded, the row end tag covers them
+        if run_tag == 'o' {
+            push_rle_run(&mut body, run_len, run_tag);
+        }
+
+        body.push('$');
+    }
+
+    body.pop(); // Drop the last row's trailing '$', the pattern terminator replaces it
+    body.push('!');
+
+    out.push_str(&body);
+    out
+}
+
+/// Parses a `.rle` pattern, returning the live cells offset from `offset`
+fn from_rle(data: &str, offset: Coord) -> HashMap<Coord, u32> {
+    let mut cells = HashMap::new();
+
+    let mut rest = data;
+    while let Some(line_end) = rest.find('\n') {
+        if !rest[..line_end].trim_start().starts_with('#') {
+            break;
+        }
+        rest = &rest[line_end + 1..];
+    }
+
+    let body = match rest.find('\n') {
+        Some(header_end) if rest[..header_end].trim_start().starts_with('x') => &rest[header_end + 1..],
+        _ => rest,
+    };
+
+    let mut x = 0;
+    let mut y = 0;
+    let mut run_len = String::new();
+
+    for c in body.chars() {
+        if c.is_ascii_digit() {
+            run_len.push(c);
+            continue;
+        }
+
+        let count = run_len.parse::<i32>().unwrap_or(1);
+        run_len.clear();
+
+        match c {
+            'b' => { x += count; },
+            'o' => {
+                for i in 0..count {
+                    cells.insert(Coord { x: offset.x + x + i, y: offset.y + y }, 0);
+                }
+                x += count;
+            },
+            '$' => { y += count; x = 0; },
+            '!' => break,
+            _ => {},
+        }
+    }
+
+    cells
+}
+
+/// Drives the Conway's Game of Life simulation: camera panning, painting, stepping, random
+/// soup seeding, rule switching and RLE persistence all live here.
+pub struct LifeState {
+    cells: HashMap<Coord, u32>,
+    cam_x: i32,
+    cam_y: i32,
+    viewport: (u32, u32),
+
+    frame_i: u64,
+    steps_per_second: u64,
+    step_frame: u64,
+
+    stepping: bool,
+    step_once: bool,
+
+    rng: Rng,
+    soup_density: f64,
+
+    rule_index: usize,
+    rule: Rule,
+
+    w_down: bool,
+    a_down: bool,
+    s_down: bool,
+    d_down: bool,
+
+    prev_lmb_coord: Option<Coord>,
+    prev_rmb_coord: Option<Coord>,
+}
+
+impl LifeState {
+    pub fn new(steps_per_second: u64) -> Self {
+        let steps_per_second = steps_per_second.clamp(MIN_STEPS_PER_SECOND, MAX_STEPS_PER_SECOND);
+
+        LifeState {
+            cells: HashMap::new(),
+            cam_x: 0,
+            cam_y: 0,
+            viewport: (800, 600),
+
+            frame_i: 0,
+            steps_per_second,
+            step_frame: FPS / steps_per_second,
+
+            stepping: true,
+            step_once: false,
+
+            rng: Rng::from_time(),
+            soup_density: 0.3,
+
+            rule_index: 0,
+            rule: Rule::parse(RULESETS[0]),
+
+            w_down: false,
+            a_down: false,
+            s_down: false,
+            d_down: false,
+
+            prev_lmb_coord: None,
+            prev_rmb_coord: None,
+        }
+    }
+}
+
+impl AppState for LifeState {
+    fn handle_event(&mut self, event: &Event) {
+        match event {
+            Event::KeyDown { keycode: Some(Keycode::W), .. } => { self.w_down = true; },
+            Event::KeyDown { keycode: Some(Keycode::A), .. } => { self.a_down = true; },
+            Event::KeyDown { keycode: Some(Keycode::S), .. } => { self.s_down = true; },
+            Event::KeyDown { keycode: Some(Keycode::D), .. } => { self.d_down = true; },
+            Event::KeyUp { keycode: Some(Keycode::W), .. } => { self.w_down = false; },
+            Event::KeyUp { keycode: Some(Keycode::A), .. } => { self.a_down = false; },
+            Event::KeyUp { keycode: Some(Keycode::S), .. } => { self.s_down = false; },
+            Event::KeyUp { keycode: Some(Keycode::D), .. } => { self.d_down = false; },
+
+            Event::KeyDown { keycode: Some(Keycode::Space), .. } => { self.stepping = !self.stepping; },
+
+            // Advance exactly one generation, pausing the simulation if it was running
+            Event::KeyDown { keycode: Some(Keycode::N), .. } => {
+                self.stepping = false;
+                self.step_once = true;
+            },
+
+            Event::KeyDown { keycode: Some(Keycode::Equals), .. } => {
+                self.steps_per_second = (self.steps_per_second + 1).min(MAX_STEPS_PER_SECOND);
+                self.step_frame = FPS / self.steps_per_second;
+            },
+            Event::KeyDown { keycode: Some(Keycode::Minus), .. } => {
+                self.steps_per_second = (self.steps_per_second - 1).max(MIN_STEPS_PER_SECOND);
+                self.step_frame = FPS / self.steps_per_second;
+            },
+
+            Event::KeyDown { keycode: Some(Keycode::Delete), .. } => { self.cells.clear(); },
+
+            Event::KeyDown { keycode: Some(Keycode::LeftBracket), .. } => {
+                self.soup_density = (self.soup_density - 0.05).max(0.05);
+            },
+            Event::KeyDown { keycode: Some(Keycode::RightBracket), .. } => {
+                self.soup_density = (self.soup_density + 0.05).min(0.95);
+            },
+            Event::KeyDown { keycode: Some(Keycode::R), .. } => {
+                let (width, height) = self.viewport;
+                let min_x = self.cam_x.div_euclid(CELL_SIZE);
+                let min_y = self.cam_y.div_euclid(CELL_SIZE);
+                let max_x = (self.cam_x + width as i32).div_euclid(CELL_SIZE);
+                let max_y = (self.cam_y + height as i32).div_euclid(CELL_SIZE);
+
+                seed_soup(&mut self.cells, &mut self.rng, min_x, min_y, max_x, max_y, self.soup_density);
+            },
+
+            Event::KeyDown { keycode: Some(Keycode::Tab), .. } => {
+                self.rule_index = (self.rule_index + 1) % RULESETS.len();
+                self.rule = Rule::parse(RULESETS[self.rule_index]);
+            },
+
+            Event::KeyDown { keycode: Some(Keycode::F5), .. } => {
+                let _ = fs::write(PATTERN_FILE, to_rle(&self.cells, RULESETS[self.rule_index]));
+            },
+            Event::KeyDown { keycode: Some(Keycode::F9), .. } => {
+                if let Ok(data) = fs::read_to_string(PATTERN_FILE) {
+                    let (width, height) = self.viewport;
+                    let center = Coord {
+                        x: (self.cam_x + width as i32 / 2).div_euclid(CELL_SIZE),
+                        y: (self.cam_y + height as i32 / 2).div_euclid(CELL_SIZE),
+                    };
+                    self.cells.extend(from_rle(&data, center));
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, _dt: Duration, input: &Input) {
+        if self.w_down { self.cam_y -= 15; }
+        if self.a_down { self.cam_x -= 15; }
+        if self.s_down { self.cam_y += 15; }
+        if self.d_down { self.cam_x += 15; }
+
+        if input.lmb_pressed {
+            // Get grid position
+            let grid_pos = mouse_to_grid(input.mouse_x, input.mouse_y, self.cam_x, self.cam_y);
+
+            // Add every cell along the line since the last sampled position, so a fast
+            // drag doesn't leave gaps between frames
+            let stroke = match self.prev_lmb_coord {
+                Some(prev) => line_between(prev, grid_pos),
+                None => vec![grid_pos],
+            };
+            for c in stroke {
+                self.cells.insert(c, 0);
+            }
+
+            self.prev_lmb_coord = Some(grid_pos);
+        } else {
+            self.prev_lmb_coord = None;
+        }
+
+        if input.rmb_pressed {
+            // Get grid position
+            let grid_pos = mouse_to_grid(input.mouse_x, input.mouse_y, self.cam_x, self.cam_y);
+
+            // Remove every cell along the line since the last sampled position
+            let stroke = match self.prev_rmb_coord {
+                Some(prev) => line_between(prev, grid_pos),
+                None => vec![grid_pos],
+            };
+            for c in stroke {
+                self.cells.remove(&c);
+            }
+
+            self.prev_rmb_coord = Some(grid_pos);
+        } else {
+            self.prev_rmb_coord = None;
+        }
+
+        if self.stepping {
+            if self.frame_i >= self.step_frame {
+                self.frame_i = 0;
+                self.cells = step(&self.cells, &self.rule);
+            }
+
+            self.frame_i += 1;
+        } else if self.step_once {
+            self.cells = step(&self.cells, &self.rule);
+        }
+
+        self.step_once = false;
+    }
+
+    fn render(&mut self, canvas: &mut WindowCanvas) -> Result<()> {
+        if let Ok(size) = canvas.output_size() {
+            self.viewport = size;
+        }
+
+        self.cells.draw(canvas, -self.cam_x, -self.cam_y)
+    }
+}